@@ -10,21 +10,24 @@
 use wapcaplet::LwcString;
 use wapcaplet::ll::{lwc_string, rust_lwc_string_ref};
 use std::libc::{c_void, c_char};
-use std::libc::types::common::c99::{uint32_t};
+use std::libc::types::common::c99::{uint32_t, uint64_t};
 use std::cast::transmute;
 use std::ptr::null;
 use ll::{c_enum, rust_enum};
 use ll_lwcstr_to_hl_lwcstr = wapcaplet::from_lwc_string;
 use ll::types::{css_language_level, CSS_LEVEL_1, CSS_LEVEL_2, CSS_LEVEL_21, CSS_LEVEL_3, CSS_LEVEL_DEFAULT};
 use types::{CssLanguageLevel, CssLevel1, CssLevel2, CssLevel21, CssLevel3, CssLevelDefault, CssLevelNotACLikeEnum};
-use ll::errors::{css_error, CSS_OK};
+use ll::errors::{css_error, CSS_OK, CSS_INVALID};
 use errors::CssError;
-use ll::properties::css_font_family_e;
+use ll::properties::{css_font_family_e, css_font_style_e, css_font_variant_e, css_font_weight_e};
 use properties::CssFontFamily;
 use ll::types::{css_color, css_unit, css_qname};
-use types::{CssColor, CssUnit, CssQName};
-use ll::stylesheet::{css_fixed, css_stylesheet_params};
-use stylesheet::{CssStylesheetParams};
+use types::{CssColor, CssUnit, CssQName, CssCounter, CssClipRect};
+use ll::stylesheet::{css_fixed, css_stylesheet_params, css_stylesheet, css_system_font};
+use ll::computed::{css_computed_counter, css_computed_clip_rect};
+use stylesheet::{CssStylesheetParams, CssUrlResolutionFn, CssImportNotificationFn, CssColorResolutionFn};
+use stylesheet::{CssFontResolutionFn, CssSystemFont, with_borrowed_ll_sheet};
+use lwcstr_from_rust_str = wapcaplet::from_rust_string;
 use ll::select::{css_pseudo_element};
 use select::{CssPseudoElement};
 use std::sys;
@@ -100,6 +103,20 @@ impl ToLl<(css_unit, css_fixed)> for CssUnit {
     }
 }
 
+// The family name is handed over with a reference that libcss is then responsible for
+impl ToLl<css_system_font> for CssSystemFont {
+    pub fn to_ll(&self) -> css_system_font {
+        css_system_font {
+            style: self.style as css_font_style_e,
+            variant: self.variant as css_font_variant_e,
+            weight: self.weight as css_font_weight_e,
+            size: self.size.to_ll_css_size(),
+            line_height: self.line_height.to_ll_css_size(),
+            family: lwcstr_from_rust_str(self.family).raw_reffed()
+        }
+    }
+}
+
 pub fn ll_unit_to_hl_unit(unit: css_unit, value: css_fixed) -> CssUnit {
     use ll::types::*;
     use types::*;
@@ -183,12 +200,21 @@ impl AsLl<css_stylesheet_params> for CssStylesheetParams {
                         inline_style: self.inline_style,
                         resolve: resolve,
                         resolve_pw: unsafe { transmute(&self.resolve) },
-                        import: null(),
-                        import_pw: null(),
-                        color: null(),
-                        color_pw: null(),
-                        font: null(),
-                        font_pw: null()
+                        import: match self.import {
+                            Some(_) => import_notification,
+                            None => null()
+                        },
+                        import_pw: unsafe { transmute(&self.import) },
+                        color: match self.color {
+                            Some(_) => color_resolution,
+                            None => null()
+                        },
+                        color_pw: unsafe { transmute(&self.color) },
+                        font: match self.font {
+                            Some(_) => font_resolution,
+                            None => null()
+                        },
+                        font_pw: unsafe { transmute(&self.font) }
                     };
                     f(&params)
                 }
@@ -197,13 +223,88 @@ impl AsLl<css_stylesheet_params> for CssStylesheetParams {
     }
 }
 
-extern fn resolve(_pw: *c_void, _base: *c_char, rel: *lwc_string, abs: *mut *lwc_string) -> css_error {
-    unsafe {
-        // TODO
-        rust_lwc_string_ref(rel);
-        *abs = rel;
+extern fn resolve(pw: *c_void, base: *c_char, rel: *lwc_string, abs: *mut *lwc_string) -> css_error {
+    let hlresolve: &Option<CssUrlResolutionFn> = unsafe { transmute(pw) };
+    match *hlresolve {
+        Some(ref f) => {
+            let hlbase = unsafe { str::raw::from_c_str(base) };
+            let hlrel = ll_lwcstr_to_hl_lwcstr(rel);
+            match (*f)(hlbase, &hlrel) {
+                Ok(hlabs) => {
+                    // libcss takes ownership of the reference
+                    unsafe { *abs = hlabs.raw_reffed(); }
+                    CSS_OK
+                }
+                Err(e) => e.to_ll()
+            }
+        }
+        None => {
+            // Without a resolver the best we can do is treat every URL as absolute
+            unsafe {
+                rust_lwc_string_ref(rel);
+                *abs = rel;
+            }
+            CSS_OK
+        }
+    }
+}
+
+extern fn import_notification(pw: *c_void, parent: *css_stylesheet, url: *lwc_string, media: *mut uint64_t) -> css_error {
+    let import: &Option<CssImportNotificationFn> = unsafe { transmute(pw) };
+    match *import {
+        Some(ref f) => {
+            let hlurl = ll_lwcstr_to_hl_lwcstr(url);
+            let result = unsafe {
+                do with_borrowed_ll_sheet(parent) |hlparent| {
+                    (*f)(hlparent, &hlurl)
+                }
+            };
+            match result {
+                Ok(hlmedia) => {
+                    unsafe { *media = hlmedia; }
+                    CSS_OK
+                }
+                Err(e) => e.to_ll()
+            }
+        }
+        None => CSS_OK
+    }
+}
+
+extern fn color_resolution(pw: *c_void, name: *lwc_string, color: *mut css_color) -> css_error {
+    let hlcolor: &Option<CssColorResolutionFn> = unsafe { transmute(pw) };
+    match *hlcolor {
+        Some(ref f) => {
+            let hlname = ll_lwcstr_to_hl_lwcstr(name);
+            match (*f)(&hlname) {
+                Ok(c) => {
+                    unsafe { *color = c.to_ll(); }
+                    CSS_OK
+                }
+                Err(e) => e.to_ll()
+            }
+        }
+        // We only hand libcss this callback when there's a closure to call
+        None => CSS_INVALID
+    }
+}
+
+extern fn font_resolution(pw: *c_void, name: *lwc_string, system_font: *mut css_system_font) -> css_error {
+    let hlfont: &Option<CssFontResolutionFn> = unsafe { transmute(pw) };
+    match *hlfont {
+        Some(ref f) => {
+            let hlname = ll_lwcstr_to_hl_lwcstr(name);
+            match (*f)(&hlname) {
+                Ok(font) => {
+                    unsafe { *system_font = font.to_ll(); }
+                    CSS_OK
+                }
+                Err(e) => e.to_ll()
+            }
+        }
+        // We only hand libcss this callback when there's a closure to call
+        None => CSS_INVALID
     }
-    CSS_OK
 }
 
 pub fn write_ll_qname(hlqname: &mut CssQName, llqname: *mut css_qname) {
@@ -229,3 +330,35 @@ pub fn lwc_string_buf_to_hl_vec(names: **lwc_string) -> ~[LwcString] {
         return result;
     }
 }
+
+pub fn ll_counters_to_hl_counters(counters: *css_computed_counter) -> ~[CssCounter] {
+    unsafe {
+        let mut result = ~[];
+        let mut counter = counters;
+        while (*counter).name.is_not_null() {
+            result.push(CssCounter {
+                name: ll_lwcstr_to_hl_lwcstr((*counter).name),
+                value: (*counter).value
+            });
+            counter = counter.offset(1);
+        }
+        result
+    }
+}
+
+pub fn ll_clip_rect_to_hl_clip_rect(rect: &css_computed_clip_rect) -> CssClipRect {
+    fn side(value: css_fixed, unit: css_unit, auto: bool) -> Option<CssUnit> {
+        if auto {
+            None
+        } else {
+            Some(ll_unit_to_hl_unit(unit, value))
+        }
+    }
+
+    CssClipRect {
+        top: side(rect.top, rect.tunit, rect.top_auto),
+        right: side(rect.right, rect.runit, rect.right_auto),
+        bottom: side(rect.bottom, rect.bunit, rect.bottom_auto),
+        left: side(rect.left, rect.lunit, rect.left_auto)
+    }
+}
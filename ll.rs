@@ -130,6 +130,11 @@ pub mod hint {
         value: css_fixed,
         unit: css_unit
     }
+
+    pub struct css_hint_position {
+        h: css_hint_length,
+        v: css_hint_length
+    }
 }
 
 pub mod properties {
@@ -409,12 +414,317 @@ pub mod properties {
     pub static CSS_LINE_HEIGHT_DIMENSION: css_line_height_e = 0x2;
     pub static CSS_LINE_HEIGHT_NORMAL: css_line_height_e = 0x3;
 
+    pub type css_background_image_e = c_enum;
+
+    pub static CSS_BACKGROUND_IMAGE_INHERIT: css_background_image_e = 0x0;
+    /* Consult pointer in struct to determine which */
+    pub static CSS_BACKGROUND_IMAGE_NONE: css_background_image_e = 0x1;
+    pub static CSS_BACKGROUND_IMAGE_IMAGE: css_background_image_e = 0x1;
+
+    pub type css_background_position_e = c_enum;
+
+    pub static CSS_BACKGROUND_POSITION_INHERIT: css_background_position_e = 0x0;
+    pub static CSS_BACKGROUND_POSITION_SET: css_background_position_e = 0x1;
+
+    pub type css_border_spacing_e = c_enum;
+
+    pub static CSS_BORDER_SPACING_INHERIT: css_border_spacing_e = 0x0;
+    pub static CSS_BORDER_SPACING_SET: css_border_spacing_e = 0x1;
+
+    pub type css_clip_e = c_enum;
+
+    pub static CSS_CLIP_INHERIT: css_clip_e = 0x0;
+    pub static CSS_CLIP_AUTO: css_clip_e = 0x1;
+    pub static CSS_CLIP_RECT: css_clip_e = 0x2;
+
+    pub type css_content_e = c_enum;
+
+    pub static CSS_CONTENT_INHERIT: css_content_e = 0x0;
+    pub static CSS_CONTENT_NONE: css_content_e = 0x1;
+    pub static CSS_CONTENT_NORMAL: css_content_e = 0x2;
+    pub static CSS_CONTENT_SET: css_content_e = 0x3;
+
+    pub type css_counter_increment_e = c_enum;
+
+    pub static CSS_COUNTER_INCREMENT_INHERIT: css_counter_increment_e = 0x0;
+    /* Consult pointer in struct to determine which */
+    pub static CSS_COUNTER_INCREMENT_NAMED: css_counter_increment_e = 0x1;
+    pub static CSS_COUNTER_INCREMENT_NONE: css_counter_increment_e = 0x1;
+
+    pub type css_counter_reset_e = c_enum;
+
+    pub static CSS_COUNTER_RESET_INHERIT: css_counter_reset_e = 0x0;
+    /* Consult pointer in struct to determine which */
+    pub static CSS_COUNTER_RESET_NAMED: css_counter_reset_e = 0x1;
+    pub static CSS_COUNTER_RESET_NONE: css_counter_reset_e = 0x1;
+
+    pub type css_letter_spacing_e = c_enum;
+
+    pub static CSS_LETTER_SPACING_INHERIT: css_letter_spacing_e = 0x0;
+    pub static CSS_LETTER_SPACING_SET: css_letter_spacing_e = 0x1;
+    pub static CSS_LETTER_SPACING_NORMAL: css_letter_spacing_e = 0x2;
+
+    pub type css_word_spacing_e = c_enum;
+
+    pub static CSS_WORD_SPACING_INHERIT: css_word_spacing_e = 0x0;
+    pub static CSS_WORD_SPACING_SET: css_word_spacing_e = 0x1;
+    pub static CSS_WORD_SPACING_NORMAL: css_word_spacing_e = 0x2;
+
+    pub type css_text_indent_e = c_enum;
+
+    pub static CSS_TEXT_INDENT_INHERIT: css_text_indent_e = 0x0;
+    pub static CSS_TEXT_INDENT_SET: css_text_indent_e = 0x1;
+
+    pub type css_top_e = c_enum;
+
+    pub static CSS_TOP_INHERIT: css_top_e = 0x0;
+    pub static CSS_TOP_SET: css_top_e = 0x1;
+    pub static CSS_TOP_AUTO: css_top_e = 0x2;
+
+    pub type css_right_e = c_enum;
+
+    pub static CSS_RIGHT_INHERIT: css_right_e = 0x0;
+    pub static CSS_RIGHT_SET: css_right_e = 0x1;
+    pub static CSS_RIGHT_AUTO: css_right_e = 0x2;
+
+    pub type css_bottom_e = c_enum;
+
+    pub static CSS_BOTTOM_INHERIT: css_bottom_e = 0x0;
+    pub static CSS_BOTTOM_SET: css_bottom_e = 0x1;
+    pub static CSS_BOTTOM_AUTO: css_bottom_e = 0x2;
+
+    pub type css_left_e = c_enum;
+
+    pub static CSS_LEFT_INHERIT: css_left_e = 0x0;
+    pub static CSS_LEFT_SET: css_left_e = 0x1;
+    pub static CSS_LEFT_AUTO: css_left_e = 0x2;
+
+    pub type css_min_height_e = c_enum;
+
+    pub static CSS_MIN_HEIGHT_INHERIT: css_min_height_e = 0x0;
+    pub static CSS_MIN_HEIGHT_SET: css_min_height_e = 0x1;
+
+    pub type css_min_width_e = c_enum;
+
+    pub static CSS_MIN_WIDTH_INHERIT: css_min_width_e = 0x0;
+    pub static CSS_MIN_WIDTH_SET: css_min_width_e = 0x1;
+
+    pub type css_max_height_e = c_enum;
+
+    pub static CSS_MAX_HEIGHT_INHERIT: css_max_height_e = 0x0;
+    pub static CSS_MAX_HEIGHT_SET: css_max_height_e = 0x1;
+    pub static CSS_MAX_HEIGHT_NONE: css_max_height_e = 0x2;
+
+    pub type css_max_width_e = c_enum;
+
+    pub static CSS_MAX_WIDTH_INHERIT: css_max_width_e = 0x0;
+    pub static CSS_MAX_WIDTH_SET: css_max_width_e = 0x1;
+    pub static CSS_MAX_WIDTH_NONE: css_max_width_e = 0x2;
+
+    pub type css_list_style_image_e = c_enum;
+
+    pub static CSS_LIST_STYLE_IMAGE_INHERIT: css_list_style_image_e = 0x0;
+    /* Consult pointer in struct to determine which */
+    pub static CSS_LIST_STYLE_IMAGE_URI: css_list_style_image_e = 0x1;
+    pub static CSS_LIST_STYLE_IMAGE_NONE: css_list_style_image_e = 0x1;
+
+    pub type css_outline_color_e = c_enum;
+
+    pub static CSS_OUTLINE_COLOR_INHERIT: css_outline_color_e = 0x0;
+    pub static CSS_OUTLINE_COLOR_COLOR: css_outline_color_e = 0x1;
+    pub static CSS_OUTLINE_COLOR_INVERT: css_outline_color_e = 0x2;
+
+    pub type css_outline_width_e = c_enum;
+
+    pub static CSS_OUTLINE_WIDTH_INHERIT: css_outline_width_e = 0x0;
+    pub static CSS_OUTLINE_WIDTH_THIN: css_outline_width_e = 0x1;
+    pub static CSS_OUTLINE_WIDTH_MEDIUM: css_outline_width_e = 0x2;
+    pub static CSS_OUTLINE_WIDTH_THICK: css_outline_width_e = 0x3;
+    pub static CSS_OUTLINE_WIDTH_WIDTH: css_outline_width_e = 0x4;
+
+    pub type css_vertical_align_e = c_enum;
+
+    pub static CSS_VERTICAL_ALIGN_INHERIT: css_vertical_align_e = 0x0;
+    pub static CSS_VERTICAL_ALIGN_BASELINE: css_vertical_align_e = 0x1;
+    pub static CSS_VERTICAL_ALIGN_SUB: css_vertical_align_e = 0x2;
+    pub static CSS_VERTICAL_ALIGN_SUPER: css_vertical_align_e = 0x3;
+    pub static CSS_VERTICAL_ALIGN_TOP: css_vertical_align_e = 0x4;
+    pub static CSS_VERTICAL_ALIGN_TEXT_TOP: css_vertical_align_e = 0x5;
+    pub static CSS_VERTICAL_ALIGN_MIDDLE: css_vertical_align_e = 0x6;
+    pub static CSS_VERTICAL_ALIGN_BOTTOM: css_vertical_align_e = 0x7;
+    pub static CSS_VERTICAL_ALIGN_TEXT_BOTTOM: css_vertical_align_e = 0x8;
+    pub static CSS_VERTICAL_ALIGN_SET: css_vertical_align_e = 0x9;
+
+    pub type css_z_index_e = c_enum;
+
+    pub static CSS_Z_INDEX_INHERIT: css_z_index_e = 0x0;
+    pub static CSS_Z_INDEX_SET: css_z_index_e = 0x1;
+    pub static CSS_Z_INDEX_AUTO: css_z_index_e = 0x2;
+
+    pub type css_orphans_e = c_enum;
+
+    pub static CSS_ORPHANS_INHERIT: css_orphans_e = 0x0;
+    pub static CSS_ORPHANS_SET: css_orphans_e = 0x1;
+
+    pub type css_widows_e = c_enum;
+
+    pub static CSS_WIDOWS_INHERIT: css_widows_e = 0x0;
+    pub static CSS_WIDOWS_SET: css_widows_e = 0x1;
+
+    pub type css_opacity_e = c_enum;
+
+    pub static CSS_OPACITY_INHERIT: css_opacity_e = 0x0;
+    pub static CSS_OPACITY_SET: css_opacity_e = 0x1;
+
+    pub type css_background_attachment_e = c_enum;
+
+    pub static CSS_BACKGROUND_ATTACHMENT_INHERIT: css_background_attachment_e = 0x0;
+    pub static CSS_BACKGROUND_ATTACHMENT_FIXED: css_background_attachment_e = 0x1;
+    pub static CSS_BACKGROUND_ATTACHMENT_SCROLL: css_background_attachment_e = 0x2;
+
+    pub type css_background_repeat_e = c_enum;
+
+    pub static CSS_BACKGROUND_REPEAT_INHERIT: css_background_repeat_e = 0x0;
+    pub static CSS_BACKGROUND_REPEAT_REPEAT_X: css_background_repeat_e = 0x1;
+    pub static CSS_BACKGROUND_REPEAT_REPEAT_Y: css_background_repeat_e = 0x2;
+    pub static CSS_BACKGROUND_REPEAT_REPEAT: css_background_repeat_e = 0x3;
+    pub static CSS_BACKGROUND_REPEAT_NO_REPEAT: css_background_repeat_e = 0x4;
+
+    pub type css_border_style_e = c_enum;
+
+    pub static CSS_BORDER_STYLE_INHERIT: css_border_style_e = 0x0;
+    pub static CSS_BORDER_STYLE_NONE: css_border_style_e = 0x1;
+    pub static CSS_BORDER_STYLE_HIDDEN: css_border_style_e = 0x2;
+    pub static CSS_BORDER_STYLE_DOTTED: css_border_style_e = 0x3;
+    pub static CSS_BORDER_STYLE_DASHED: css_border_style_e = 0x4;
+    pub static CSS_BORDER_STYLE_SOLID: css_border_style_e = 0x5;
+    pub static CSS_BORDER_STYLE_DOUBLE: css_border_style_e = 0x6;
+    pub static CSS_BORDER_STYLE_GROOVE: css_border_style_e = 0x7;
+    pub static CSS_BORDER_STYLE_RIDGE: css_border_style_e = 0x8;
+    pub static CSS_BORDER_STYLE_INSET: css_border_style_e = 0x9;
+    pub static CSS_BORDER_STYLE_OUTSET: css_border_style_e = 0xa;
+
+    pub type css_border_collapse_e = c_enum;
+
+    pub static CSS_BORDER_COLLAPSE_INHERIT: css_border_collapse_e = 0x0;
+    pub static CSS_BORDER_COLLAPSE_SEPARATE: css_border_collapse_e = 0x1;
+    pub static CSS_BORDER_COLLAPSE_COLLAPSE: css_border_collapse_e = 0x2;
+
+    pub type css_outline_style_e = c_enum;
+
+    pub static CSS_OUTLINE_STYLE_INHERIT: css_outline_style_e = 0x0;
+    pub static CSS_OUTLINE_STYLE_NONE: css_outline_style_e = 0x1;
+    pub static CSS_OUTLINE_STYLE_DOTTED: css_outline_style_e = 0x3;
+    pub static CSS_OUTLINE_STYLE_DASHED: css_outline_style_e = 0x4;
+    pub static CSS_OUTLINE_STYLE_SOLID: css_outline_style_e = 0x5;
+    pub static CSS_OUTLINE_STYLE_DOUBLE: css_outline_style_e = 0x6;
+    pub static CSS_OUTLINE_STYLE_GROOVE: css_outline_style_e = 0x7;
+    pub static CSS_OUTLINE_STYLE_RIDGE: css_outline_style_e = 0x8;
+    pub static CSS_OUTLINE_STYLE_INSET: css_outline_style_e = 0x9;
+    pub static CSS_OUTLINE_STYLE_OUTSET: css_outline_style_e = 0xa;
+
+    pub type css_list_style_type_e = c_enum;
+
+    pub static CSS_LIST_STYLE_TYPE_INHERIT: css_list_style_type_e = 0x0;
+    pub static CSS_LIST_STYLE_TYPE_DISC: css_list_style_type_e = 0x1;
+    pub static CSS_LIST_STYLE_TYPE_CIRCLE: css_list_style_type_e = 0x2;
+    pub static CSS_LIST_STYLE_TYPE_SQUARE: css_list_style_type_e = 0x3;
+    pub static CSS_LIST_STYLE_TYPE_DECIMAL: css_list_style_type_e = 0x4;
+    pub static CSS_LIST_STYLE_TYPE_DECIMAL_LEADING_ZERO: css_list_style_type_e = 0x5;
+    pub static CSS_LIST_STYLE_TYPE_LOWER_ROMAN: css_list_style_type_e = 0x6;
+    pub static CSS_LIST_STYLE_TYPE_UPPER_ROMAN: css_list_style_type_e = 0x7;
+    pub static CSS_LIST_STYLE_TYPE_LOWER_GREEK: css_list_style_type_e = 0x8;
+    pub static CSS_LIST_STYLE_TYPE_LOWER_LATIN: css_list_style_type_e = 0x9;
+    pub static CSS_LIST_STYLE_TYPE_UPPER_LATIN: css_list_style_type_e = 0xa;
+    pub static CSS_LIST_STYLE_TYPE_ARMENIAN: css_list_style_type_e = 0xb;
+    pub static CSS_LIST_STYLE_TYPE_GEORGIAN: css_list_style_type_e = 0xc;
+    pub static CSS_LIST_STYLE_TYPE_LOWER_ALPHA: css_list_style_type_e = 0xd;
+    pub static CSS_LIST_STYLE_TYPE_UPPER_ALPHA: css_list_style_type_e = 0xe;
+    pub static CSS_LIST_STYLE_TYPE_NONE: css_list_style_type_e = 0xf;
+
+    pub type css_list_style_position_e = c_enum;
+
+    pub static CSS_LIST_STYLE_POSITION_INHERIT: css_list_style_position_e = 0x0;
+    pub static CSS_LIST_STYLE_POSITION_INSIDE: css_list_style_position_e = 0x1;
+    pub static CSS_LIST_STYLE_POSITION_OUTSIDE: css_list_style_position_e = 0x2;
+
+    pub type css_text_transform_e = c_enum;
+
+    pub static CSS_TEXT_TRANSFORM_INHERIT: css_text_transform_e = 0x0;
+    pub static CSS_TEXT_TRANSFORM_CAPITALIZE: css_text_transform_e = 0x1;
+    pub static CSS_TEXT_TRANSFORM_UPPERCASE: css_text_transform_e = 0x2;
+    pub static CSS_TEXT_TRANSFORM_LOWERCASE: css_text_transform_e = 0x3;
+    pub static CSS_TEXT_TRANSFORM_NONE: css_text_transform_e = 0x4;
+
+    pub type css_white_space_e = c_enum;
+
+    pub static CSS_WHITE_SPACE_INHERIT: css_white_space_e = 0x0;
+    pub static CSS_WHITE_SPACE_NORMAL: css_white_space_e = 0x1;
+    pub static CSS_WHITE_SPACE_PRE: css_white_space_e = 0x2;
+    pub static CSS_WHITE_SPACE_NOWRAP: css_white_space_e = 0x3;
+    pub static CSS_WHITE_SPACE_PRE_WRAP: css_white_space_e = 0x4;
+    pub static CSS_WHITE_SPACE_PRE_LINE: css_white_space_e = 0x5;
+
+    pub type css_direction_e = c_enum;
+
+    pub static CSS_DIRECTION_INHERIT: css_direction_e = 0x0;
+    pub static CSS_DIRECTION_LTR: css_direction_e = 0x1;
+    pub static CSS_DIRECTION_RTL: css_direction_e = 0x2;
+
+    pub type css_unicode_bidi_e = c_enum;
+
+    pub static CSS_UNICODE_BIDI_INHERIT: css_unicode_bidi_e = 0x0;
+    pub static CSS_UNICODE_BIDI_NORMAL: css_unicode_bidi_e = 0x1;
+    pub static CSS_UNICODE_BIDI_EMBED: css_unicode_bidi_e = 0x2;
+    pub static CSS_UNICODE_BIDI_BIDI_OVERRIDE: css_unicode_bidi_e = 0x3;
+
+    // libcss computes no aural properties, so this only describes the
+    // voice-family hint: a list of names plus a generic voice, if any
+    pub type css_voice_family_e = c_enum;
+
+    pub static CSS_VOICE_FAMILY_NONE: css_voice_family_e = 0x0;
+    pub static CSS_VOICE_FAMILY_MALE: css_voice_family_e = 0x1;
+    pub static CSS_VOICE_FAMILY_FEMALE: css_voice_family_e = 0x2;
+    pub static CSS_VOICE_FAMILY_CHILD: css_voice_family_e = 0x3;
+
+    pub type css_column_count_e = c_enum;
+
+    pub static CSS_COLUMN_COUNT_INHERIT: css_column_count_e = 0x0;
+    pub static CSS_COLUMN_COUNT_AUTO: css_column_count_e = 0x1;
+    pub static CSS_COLUMN_COUNT_SET: css_column_count_e = 0x2;
+
+    pub type css_column_gap_e = c_enum;
+
+    pub static CSS_COLUMN_GAP_INHERIT: css_column_gap_e = 0x0;
+    pub static CSS_COLUMN_GAP_SET: css_column_gap_e = 0x1;
+    pub static CSS_COLUMN_GAP_NORMAL: css_column_gap_e = 0x2;
+
+    pub type css_column_rule_color_e = c_enum;
+
+    pub static CSS_COLUMN_RULE_COLOR_INHERIT: css_column_rule_color_e = 0x0;
+    pub static CSS_COLUMN_RULE_COLOR_COLOR: css_column_rule_color_e = 0x1;
+    pub static CSS_COLUMN_RULE_COLOR_CURRENT_COLOR: css_column_rule_color_e = 0x2;
+
+    pub type css_column_rule_width_e = c_enum;
+
+    pub static CSS_COLUMN_RULE_WIDTH_INHERIT: css_column_rule_width_e = 0x0;
+    pub static CSS_COLUMN_RULE_WIDTH_THIN: css_column_rule_width_e = 0x1;
+    pub static CSS_COLUMN_RULE_WIDTH_MEDIUM: css_column_rule_width_e = 0x2;
+    pub static CSS_COLUMN_RULE_WIDTH_THICK: css_column_rule_width_e = 0x3;
+    pub static CSS_COLUMN_RULE_WIDTH_WIDTH: css_column_rule_width_e = 0x4;
+
+    pub type css_column_width_e = c_enum;
+
+    pub static CSS_COLUMN_WIDTH_INHERIT: css_column_width_e = 0x0;
+    pub static CSS_COLUMN_WIDTH_SET: css_column_width_e = 0x1;
+    pub static CSS_COLUMN_WIDTH_AUTO: css_column_width_e = 0x2;
+
 }
 
 pub mod stylesheet {
 
     use std::libc::{c_char, c_void, size_t};
-    use std::libc::types::common::c99::{uint32_t, int32_t, uint8_t};
+    use std::libc::types::common::c99::{uint32_t, int32_t, uint8_t, uint64_t};
     use wapcaplet::ll::lwc_string;
     use ll::types::{css_language_level, css_unit};
     use ll::properties::{css_font_style_e, css_font_variant_e, css_font_weight_e};
@@ -448,7 +758,7 @@ pub mod stylesheet {
 
     pub type css_stylesheet = c_void;
 
-    struct css_system_font {
+    pub struct css_system_font {
         style: css_font_style_e,
         variant: css_font_variant_e,
         weight: css_font_weight_e,
@@ -474,6 +784,10 @@ pub mod stylesheet {
         fn css_stylesheet_size(sheet: *css_stylesheet, size: *mut size_t) -> css_error;
         fn css_stylesheet_append_data(sheet: *css_stylesheet, data: *uint8_t, len: size_t) -> css_error;
         fn css_stylesheet_data_done(sheet: *css_stylesheet) -> css_error;
+        fn css_stylesheet_next_pending_import(parent: *css_stylesheet,
+                                              url: *mut *lwc_string,
+                                              media: *mut uint64_t) -> css_error;
+        fn css_stylesheet_register_import(parent: *css_stylesheet, child: *css_stylesheet) -> css_error;
     }
 }
 
@@ -570,6 +884,67 @@ pub mod computed {
 
     pub type compute_font_size_cb = *u8; // (pw: *c_void, parent: *css_hint, size: *mut css_hint) -> css_error
 
+    // Arrays of these are terminated by an entry with a NULL name
+    pub struct css_computed_counter {
+        name: *lwc_string,
+        value: css_fixed
+    }
+
+    pub static CSS_COMPUTED_CONTENT_NONE: uint8_t = 0;
+    pub static CSS_COMPUTED_CONTENT_STRING: uint8_t = 1;
+    pub static CSS_COMPUTED_CONTENT_URI: uint8_t = 2;
+    pub static CSS_COMPUTED_CONTENT_COUNTER: uint8_t = 3;
+    pub static CSS_COMPUTED_CONTENT_COUNTERS: uint8_t = 4;
+    pub static CSS_COMPUTED_CONTENT_ATTR: uint8_t = 5;
+    pub static CSS_COMPUTED_CONTENT_OPEN_QUOTE: uint8_t = 6;
+    pub static CSS_COMPUTED_CONTENT_CLOSE_QUOTE: uint8_t = 7;
+    pub static CSS_COMPUTED_CONTENT_NO_OPEN_QUOTE: uint8_t = 8;
+    pub static CSS_COMPUTED_CONTENT_NO_CLOSE_QUOTE: uint8_t = 9;
+
+    // The C type is a struct holding a union, with the item type picking the
+    // member. The union is declared here as its largest member, counters();
+    // read it through the view for the item's type. Arrays of these are
+    // terminated by an entry of type CSS_COMPUTED_CONTENT_NONE
+    pub struct css_computed_content_item {
+        item_type: uint8_t,
+        data: css_computed_content_counters
+    }
+
+    // The member for strings, URIs and attr()
+    pub struct css_computed_content_string {
+        string: *lwc_string
+    }
+
+    // The member for counter()
+    pub struct css_computed_content_counter {
+        name: *lwc_string,
+        style: uint8_t
+    }
+
+    // The member for counters()
+    pub struct css_computed_content_counters {
+        name: *lwc_string,
+        sep: *lwc_string,
+        style: uint8_t
+    }
+
+    pub struct css_computed_clip_rect {
+        top: css_fixed,
+        right: css_fixed,
+        bottom: css_fixed,
+        left: css_fixed,
+
+        tunit: css_unit,
+        runit: css_unit,
+        bunit: css_unit,
+        lunit: css_unit,
+
+        top_auto: bool,
+        right_auto: bool,
+        bottom_auto: bool,
+        left_auto: bool
+    }
+
     pub extern {
         fn css_computed_style_compose(parent: *css_computed_style,
                                       child: *css_computed_style,
@@ -607,5 +982,45 @@ pub mod computed {
         fn css_computed_text_align(style: *css_computed_style) -> uint8_t;
         fn css_computed_text_decoration(style: *css_computed_style) -> uint8_t;
         fn css_computed_line_height(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_background_image(style: *css_computed_style, url: *mut *lwc_string) -> uint8_t;
+        fn css_computed_background_repeat(style: *css_computed_style) -> uint8_t;
+        fn css_computed_background_attachment(style: *css_computed_style) -> uint8_t;
+        fn css_computed_background_position(style: *css_computed_style,
+                                            hlength: *mut css_fixed, hunit: *mut css_unit,
+                                            vlength: *mut css_fixed, vunit: *mut css_unit) -> uint8_t;
+        fn css_computed_border_top_style(style: *css_computed_style) -> uint8_t;
+        fn css_computed_border_right_style(style: *css_computed_style) -> uint8_t;
+        fn css_computed_border_bottom_style(style: *css_computed_style) -> uint8_t;
+        fn css_computed_border_left_style(style: *css_computed_style) -> uint8_t;
+        fn css_computed_border_collapse(style: *css_computed_style) -> uint8_t;
+        fn css_computed_border_spacing(style: *css_computed_style,
+                                       hlength: *mut css_fixed, hunit: *mut css_unit,
+                                       vlength: *mut css_fixed, vunit: *mut css_unit) -> uint8_t;
+        fn css_computed_top(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_right(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_bottom(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_left(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_min_height(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_min_width(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_max_height(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_max_width(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_outline_color(style: *css_computed_style, color: *mut css_color) -> uint8_t;
+        fn css_computed_outline_style(style: *css_computed_style) -> uint8_t;
+        fn css_computed_outline_width(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_list_style_type(style: *css_computed_style) -> uint8_t;
+        fn css_computed_list_style_position(style: *css_computed_style) -> uint8_t;
+        fn css_computed_list_style_image(style: *css_computed_style, url: *mut *lwc_string) -> uint8_t;
+        fn css_computed_content(style: *css_computed_style, content: *mut *css_computed_content_item) -> uint8_t;
+        fn css_computed_quotes(style: *css_computed_style, quotes: *mut **lwc_string) -> uint8_t;
+        fn css_computed_counter_increment(style: *css_computed_style, counters: *mut *css_computed_counter) -> uint8_t;
+        fn css_computed_counter_reset(style: *css_computed_style, counters: *mut *css_computed_counter) -> uint8_t;
+        fn css_computed_letter_spacing(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_word_spacing(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_text_indent(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_text_transform(style: *css_computed_style) -> uint8_t;
+        fn css_computed_white_space(style: *css_computed_style) -> uint8_t;
+        fn css_computed_vertical_align(style: *css_computed_style, length: *mut css_fixed, unit: *mut css_unit) -> uint8_t;
+        fn css_computed_direction(style: *css_computed_style) -> uint8_t;
+        fn css_computed_unicode_bidi(style: *css_computed_style) -> uint8_t;
     }
 }
@@ -12,11 +12,15 @@ mod example1 {
 
     use CssResult;
     use CssProperty;
+    use properties::CssPropColor;
     use types::*;
     use hint::*;
     use select::*;
-    use util::VoidPtrLike;
-    use wapcaplet::LwcString;
+    use stylesheet::*;
+    use computed::CssComputedStyle;
+    use util::{VoidPtrLike, rust_str_to_net_qname};
+    use wapcaplet::{LwcString, from_rust_string};
+    use std::ascii::StrAsciiExt;
     use std::libc;
     use std::cast;
 
@@ -45,11 +49,11 @@ mod example1 {
     fn run() {
         use super::super::stylesheet::{CssUrlResolutionFn, CssStylesheetParams, CssStylesheet,
                                        css_stylesheet_create, CssStylesheetParamsVersion1};
+        use super::super::stylesheet::{CssStylesheetComplete, CssStylesheetImportsPending};
         use super::super::computed::CssComputedStyle;
         use super::super::values::{CssColorColor, CssColorInherit};
         use super::super::ll::types::{CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL, CSS_MEDIA_SCREEN};
         use super::super::conversions::ToLl;
-        use wapcaplet::{LwcString, from_rust_string};
 
         let data = "h1 { color: red; }\
                     h4 { color: #321; }\
@@ -71,20 +75,24 @@ mod example1 {
             font: None,
         };
 
-        let mut sheet: CssStylesheet = css_stylesheet_create(&params);
+        let mut sheet: CssStylesheet = css_stylesheet_create(params).unwrap();
         debug!("stylesheet: %?", sheet);
         debug!("stylesheet size: %?", sheet.size());
 
-        sheet.append_data(data.as_bytes().to_owned());
-        sheet.data_done();
+        sheet.append_data(data.as_bytes().to_owned()).unwrap();
+        match sheet.data_done() {
+            Ok(CssStylesheetComplete) => (),
+            Ok(CssStylesheetImportsPending) => fail!(~"no imports were expected"),
+            Err(e) => fail!(fmt!("finishing parsing: %?", e))
+        }
         debug!("stylesheet size: %?", sheet.size());
 
 
-        let mut select_ctx: CssSelectCtx = css_select_ctx_create();
-        assert!(select_ctx.count_sheets() == 0);
-        select_ctx.append_sheet(sheet, CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL);
+        let mut select_ctx: CssSelectCtx = css_select_ctx_create().unwrap();
+        assert!(select_ctx.count_sheets().unwrap() == 0);
+        select_ctx.append_sheet(sheet, CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL).unwrap();
         debug!("count sheets: %?", select_ctx.count_sheets());
-        assert!(select_ctx.count_sheets() == 1);
+        assert!(select_ctx.count_sheets().unwrap() == 1);
 
         for ::std::uint::range(1, 7) |hh| {
             let element = fmt!("h%u", hh);
@@ -94,9 +102,17 @@ mod example1 {
             let style: CssSelectResults = select_ctx.select_style(&node,
                                                                   CSS_MEDIA_SCREEN,
                                                                   None,
-                                                                  &select_handler);
+                                                                  &select_handler).unwrap();
 
-            let computed: CssComputedStyle = style.computed_style(CssPseudoElementNone);
+            let computed: CssComputedStyle = style.computed_style(CssPseudoElementNone).unwrap();
+            // Nothing generates ::before, or any other pseudo element
+            assert!(style.computed_style(CssPseudoElementBefore).is_none());
+            assert!(style.computed_style(CssPseudoElementCount).is_none());
+            let mut generated = 0;
+            for style.pseudo_element_styles().advance |_| {
+                generated += 1;
+            }
+            assert!(generated == 0);
 
             match computed.color() {
                 CssColorInherit => {
@@ -109,10 +125,229 @@ mod example1 {
         }
     }
 
+    #[test]
+    fn inline_style() {
+        use super::super::values::{CssColorColor, CssColorInherit};
+        use super::super::ll::types::{CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL, CSS_MEDIA_SCREEN};
+
+        let sheet = css_stylesheet_create_from_str(sheet_params(None, None), "h1#title { color: #010203 }");
+        let mut select_ctx = css_select_ctx_create().unwrap();
+        select_ctx.append_sheet(sheet.unwrap(), CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL).unwrap();
+
+        let node = MyDomNode { name: @from_rust_string("h1") };
+        let handler = SelectHandler { bogus: () };
+        let color_with = |inline: Option<&CssStylesheet>| -> CssColor {
+            let style = select_ctx.select_style(&node, CSS_MEDIA_SCREEN, inline, &handler).unwrap();
+            match style.computed_style(CssPseudoElementNone).unwrap().color() {
+                CssColorColor(color) => color,
+                CssColorInherit => fail!(~"expected a color")
+            }
+        };
+
+        // The id selector applies on its own...
+        let color = color_with(None);
+        assert!(color.r == 0x01 && color.g == 0x02 && color.b == 0x03);
+
+        // ...but the style attribute beats it, however specific it is
+        let inline = css_stylesheet_create_inline("foo", "color: #abcdef", None).unwrap();
+        let color = color_with(Some(&inline));
+        assert!(color.r == 0xab && color.g == 0xcd && color.b == 0xef);
+    }
+
     fn resolve_url(_base: &str, _rel: &LwcString) -> CssResult<LwcString> {
         fail!(~"resolving url");
     }
 
+    fn sheet_params(color: Option<CssColorResolutionFn>,
+                    font: Option<CssFontResolutionFn>) -> CssStylesheetParams {
+        CssStylesheetParams {
+            params_version: CssStylesheetParamsVersion1,
+            level: CssLevel21,
+            charset: ~"UTF-8",
+            url: ~"foo",
+            title: ~"foo",
+            allow_quirks: false,
+            inline_style: false,
+            resolve: None,
+            import: None,
+            color: color,
+            font: font,
+        }
+    }
+
+    // Cascades `data` as the only author sheet and hands over the style of an element called `name`
+    fn with_selected_style(params: CssStylesheetParams, data: &str, name: &str,
+                           f: &fn(&CssComputedStyle)) {
+        use super::super::ll::types::{CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL, CSS_MEDIA_SCREEN};
+
+        let mut sheet = css_stylesheet_create(params).unwrap();
+        sheet.append_data(data.as_bytes()).unwrap();
+        sheet.data_done().unwrap();
+
+        let mut select_ctx = css_select_ctx_create().unwrap();
+        select_ctx.append_sheet(sheet, CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL).unwrap();
+
+        let node = MyDomNode { name: @from_rust_string(name) };
+        let handler = SelectHandler { bogus: () };
+        let style = select_ctx.select_style(&node, CSS_MEDIA_SCREEN, None, &handler).unwrap();
+        f(&style.computed_style(CssPseudoElementNone).unwrap());
+    }
+
+    #[test]
+    fn pseudo_element_styles() {
+        use super::super::ll::types::{CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL, CSS_MEDIA_SCREEN};
+
+        let sheet = css_stylesheet_create_from_str(sheet_params(None, None), "p:before { content: \"x\" }");
+        let mut select_ctx = css_select_ctx_create().unwrap();
+        select_ctx.append_sheet(sheet.unwrap(), CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL).unwrap();
+
+        let handler = SelectHandler { bogus: () };
+        let generated = |name: &str| -> ~[uint] {
+            let node = MyDomNode { name: @from_rust_string(name) };
+            let style = select_ctx.select_style(&node, CSS_MEDIA_SCREEN, None, &handler).unwrap();
+            let mut elements = ~[];
+            for style.pseudo_element_styles().advance |(element, _)| {
+                elements.push(element as uint);
+            }
+            elements
+        };
+
+        assert!(generated("h1").is_empty());
+        assert!(generated("p") == ~[CssPseudoElementBefore as uint]);
+    }
+
+    #[test]
+    fn url_resolution() {
+        use super::super::values::CssBackgroundImageImage;
+
+        let resolve: CssUrlResolutionFn = |base, rel| {
+            Ok(from_rust_string(base + "/" + rel.to_str_slice()))
+        };
+        let mut params = sheet_params(None, None);
+        params.url = ~"http://example.com/css";
+        params.resolve = Some(resolve);
+
+        do with_selected_style(params, "p { background-image: url(bg.png) }", "p") |computed| {
+            match computed.background_image() {
+                CssBackgroundImageImage(url) => {
+                    assert!(url.to_str_slice() == "http://example.com/css/bg.png")
+                }
+                _ => fail!(~"expected the resolved image")
+            }
+        }
+    }
+
+    #[test]
+    fn color_resolution() {
+        use super::super::errors::CssInvalid;
+        use super::super::values::{CssColorColor, CssColorInherit};
+
+        let color: CssColorResolutionFn = |name| {
+            if name.to_str_slice().to_ascii_lower() == ~"buttonface" {
+                Ok(CssColor { a: 255, r: 0x12, g: 0x34, b: 0x56 })
+            } else {
+                Err(CssInvalid)
+            }
+        };
+
+        do with_selected_style(sheet_params(Some(color), None), "p { color: ButtonFace }", "p") |computed| {
+            match computed.color() {
+                CssColorColor(color) => assert!(color.r == 0x12 && color.g == 0x34 && color.b == 0x56),
+                CssColorInherit => fail!(~"expected the resolved system color")
+            }
+        }
+    }
+
+    #[test]
+    fn html_ua_sheet() {
+        use super::super::values::{CssDisplayBlock, CssDisplayListItem, CssDisplayNone};
+        use super::super::values::CssFontSizeDimension;
+        use super::super::ll::types::CSS_MEDIA_SCREEN;
+        use super::super::util::float_to_css_fixed;
+
+        let select_ctx = CssSelectCtx::with_html_ua_sheet().unwrap();
+        assert!(select_ctx.count_sheets().unwrap() == 1);
+
+        let handler = SelectHandler { bogus: () };
+        let select = |name: &str| -> CssSelectResults {
+            let node = MyDomNode { name: @from_rust_string(name) };
+            select_ctx.select_style(&node, CSS_MEDIA_SCREEN, None, &handler).unwrap()
+        };
+
+        let head = select("head");
+        assert!(head.computed_style(CssPseudoElementNone).unwrap().display(false) as uint == CssDisplayNone as uint);
+        let li = select("li");
+        assert!(li.computed_style(CssPseudoElementNone).unwrap().display(false) as uint == CssDisplayListItem as uint);
+
+        let h1 = select("h1");
+        let computed = h1.computed_style(CssPseudoElementNone).unwrap();
+        assert!(computed.display(false) as uint == CssDisplayBlock as uint);
+        // 2em of the 16px default, made absolute because the node has no parent
+        match computed.font_size() {
+            CssFontSizeDimension(CssUnitPx(size)) => assert!(size == float_to_css_fixed(32.0)),
+            _ => fail!(~"expected h1 to be 2em")
+        }
+    }
+
+    #[test]
+    fn presentational_hints() {
+        use super::super::values::{CssColorColor, CssColorInherit};
+
+        // The hint applies when no author rule does
+        do with_selected_style(sheet_params(None, None), "p { color: red }", "font") |computed| {
+            match computed.color() {
+                CssColorColor(color) => assert!(color.r == 0x0a && color.g == 0x0b && color.b == 0x0c),
+                CssColorInherit => fail!(~"expected the presentational color")
+            }
+        }
+
+        // and author rules win over it
+        do with_selected_style(sheet_params(None, None), "font { color: #123456 }", "font") |computed| {
+            match computed.color() {
+                CssColorColor(color) => assert!(color.r == 0x12 && color.g == 0x34 && color.b == 0x56),
+                CssColorInherit => fail!(~"expected the author color")
+            }
+        }
+    }
+
+    #[test]
+    fn font_resolution() {
+        use super::super::errors::CssInvalid;
+        use super::super::properties::{CssFontStyleNormal, CssFontVariantNormal, CssFontWeightNormal};
+        use super::super::values::{CssFontSizeDimension, CssFontFamilyValue};
+        use super::super::util::float_to_css_fixed;
+
+        let font: CssFontResolutionFn = |name| {
+            if name.to_str_slice().to_ascii_lower() == ~"menu" {
+                Ok(CssSystemFont {
+                    style: CssFontStyleNormal,
+                    variant: CssFontVariantNormal,
+                    weight: CssFontWeightNormal,
+                    size: CssUnitPt(float_to_css_fixed(9.0)),
+                    line_height: CssUnitEm(float_to_css_fixed(1.2)),
+                    family: ~"Menu Sans"
+                })
+            } else {
+                Err(CssInvalid)
+            }
+        };
+
+        do with_selected_style(sheet_params(None, Some(font)), "p { font: menu }", "p") |computed| {
+            match computed.font_size() {
+                // The system font's 9pt, made absolute in px
+                CssFontSizeDimension(CssUnitPx(size)) => assert!(size == float_to_css_fixed(12.0)),
+                _ => fail!(~"expected the system font's size")
+            }
+            match computed.font_family() {
+                CssFontFamilyValue(names) => {
+                    assert!(names.len() == 1);
+                    assert!(names[0].to_str_slice() == "Menu Sans");
+                }
+                _ => fail!(~"expected the system font's family")
+            }
+        }
+    }
+
     struct SelectHandler {
         bogus: ()
     }
@@ -130,7 +365,14 @@ mod example1 {
 
         fn node_classes(&self, _node: &MyDomNode) -> Option<~[LwcString]> { None }
 
-        fn node_id(&self, _node: &MyDomNode) -> Option<LwcString> { None }
+        // As if every <h1> had id="title"
+        fn node_id(&self, node: &MyDomNode) -> Option<LwcString> {
+            if (*node.name).to_str_slice() == "h1" {
+                Some(from_rust_string("title"))
+            } else {
+                None
+            }
+        }
 
         fn named_parent_node(&self, _node: &MyDomNode, _qname: &CssQName) -> Option<MyDomNode> {
             None
@@ -139,10 +381,25 @@ mod example1 {
         fn parent_node(&self, _node: &MyDomNode) -> Option<MyDomNode> {
             None
         }
+
+        fn sibling_node(&self, _node: &MyDomNode) -> Option<MyDomNode> {
+            None
+        }
+
+        fn next_sibling_node(&self, _node: &MyDomNode) -> Option<MyDomNode> {
+            None
+        }
+
+        fn node_is_empty(&self, _node: &MyDomNode) -> bool { true }
         
         fn node_has_class(&self, _node: &MyDomNode, _name: LwcString) -> bool { false }
 
-        fn node_has_id(&self, _node: &MyDomNode, _name: LwcString) -> bool { false }
+        fn node_has_id(&self, node: &MyDomNode, name: LwcString) -> bool {
+            match self.node_id(node) {
+                Some(id) => id.to_str_slice() == name.to_str_slice(),
+                None => false
+            }
+        }
 
         fn named_ancestor_node(&self, _node: &MyDomNode, _qname: &CssQName) -> Option<MyDomNode> {
             None
@@ -154,11 +411,288 @@ mod example1 {
 
         fn node_is_visited(&self, _node: &MyDomNode) -> bool { false }
 
+        // As if the pointer were over every <a>
+        fn node_is_hover(&self, node: &MyDomNode) -> bool {
+            (*node.name).to_str_slice() == "a"
+        }
+
         fn ua_default_for_property(&self, property: CssProperty) -> CssHint {
             match property {
                 _ => CssHintDefault
             }
         }
+
+        // As if every <font> had color="#0a0b0c"
+        fn presentational_hint(&self, node: &MyDomNode, property: CssProperty) -> Option<CssHint> {
+            if (*node.name).to_str_slice() == "font" && property as uint == CssPropColor as uint {
+                Some(CssHintColor(CssColor { a: 255, r: 0x0a, g: 0x0b, b: 0x0c }))
+            } else {
+                None
+            }
+        }
+
+        fn node_language(&self, node: &MyDomNode) -> Option<LwcString> {
+            self.node_attribute(node, &rust_str_to_net_qname("lang"))
+        }
+
+        fn node_attribute(&self, _node: &MyDomNode, qname: &CssQName) -> Option<LwcString> {
+            let name = qname.name.to_str_slice();
+            if name == "lang" {
+                Some(from_rust_string("en-US"))
+            } else if name == "href" {
+                Some(from_rust_string("http://example.com/doc.pdf"))
+            } else if name == "rel" {
+                Some(from_rust_string("nofollow noopener"))
+            } else {
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn content_items() {
+        use super::super::values::*;
+
+        let sheet = "p { content: \"x\" counter(c, upper-roman) counters(c, \".\") attr(title) open-quote }";
+        do with_selected_style(sheet_params(None, None), sheet, "p") |computed| {
+            let items = match computed.content() {
+                CssContentSet(items) => items,
+                _ => fail!(~"expected a list of content items")
+            };
+            assert!(items.len() == 5);
+            match items[0] {
+                CssContentString(ref s) => assert!(s.to_str_slice() == "x"),
+                _ => fail!(~"expected a string")
+            }
+            match items[1] {
+                CssContentCounter(ref name, style) => {
+                    assert!(name.to_str_slice() == "c");
+                    assert!(style as uint == CssListStyleTypeUpperRoman as uint);
+                }
+                _ => fail!(~"expected counter()")
+            }
+            match items[2] {
+                CssContentCounters(ref name, ref sep, style) => {
+                    assert!(name.to_str_slice() == "c");
+                    assert!(sep.to_str_slice() == ".");
+                    assert!(style as uint == CssListStyleTypeDecimal as uint);
+                }
+                _ => fail!(~"expected counters()")
+            }
+            match items[3] {
+                CssContentAttr(ref name) => assert!(name.to_str_slice() == "title"),
+                _ => fail!(~"expected attr()")
+            }
+            match items[4] {
+                CssContentOpenQuote => (),
+                _ => fail!(~"expected open-quote")
+            }
+        }
+    }
+
+    #[test]
+    fn background() {
+        use super::super::values::{CssBackgroundImageImage, CssBackgroundRepeatRepeatY};
+        use super::super::values::{CssBackgroundAttachmentFixed, CssBackgroundPositionSet};
+        use super::super::util::float_to_css_fixed;
+
+        let sheet = "p { background: url(http://example.com/bg.png) repeat-y fixed 10px 20% }";
+        do with_selected_style(sheet_params(None, None), sheet, "p") |computed| {
+            match computed.background_image() {
+                CssBackgroundImageImage(url) => assert!(url.to_str_slice() == "http://example.com/bg.png"),
+                _ => fail!(~"expected an image")
+            }
+            assert!(computed.background_repeat() as uint == CssBackgroundRepeatRepeatY as uint);
+            assert!(computed.background_attachment() as uint == CssBackgroundAttachmentFixed as uint);
+            match computed.background_position() {
+                CssBackgroundPositionSet(CssUnitPx(h), CssUnitPct(v)) => {
+                    assert!(h == float_to_css_fixed(10.0));
+                    assert!(v == float_to_css_fixed(20.0));
+                }
+                _ => fail!(~"expected the horizontal length, then the vertical percentage")
+            }
+        }
+    }
+
+    #[test]
+    fn border_styles() {
+        use super::super::values::{CssBorderStyleSolid, CssBorderStyleDashed, CssBorderStyleDotted};
+        use super::super::values::{CssBorderStyleDouble, CssBorderCollapseCollapse, CssBorderSpacingSet};
+        use super::super::util::float_to_css_fixed;
+
+        let sheet = "p { border-style: solid dashed dotted double }
+                     p { border-collapse: collapse; border-spacing: 1px 2px }";
+        do with_selected_style(sheet_params(None, None), sheet, "p") |computed| {
+            assert!(computed.border_top_style() as uint == CssBorderStyleSolid as uint);
+            assert!(computed.border_right_style() as uint == CssBorderStyleDashed as uint);
+            assert!(computed.border_bottom_style() as uint == CssBorderStyleDotted as uint);
+            assert!(computed.border_left_style() as uint == CssBorderStyleDouble as uint);
+            assert!(computed.border_collapse() as uint == CssBorderCollapseCollapse as uint);
+            match computed.border_spacing() {
+                CssBorderSpacingSet(CssUnitPx(h), CssUnitPx(v)) => {
+                    assert!(h == float_to_css_fixed(1.0));
+                    assert!(v == float_to_css_fixed(2.0));
+                }
+                _ => fail!(~"expected the horizontal spacing, then the vertical")
+            }
+        }
+    }
+
+    #[test]
+    fn offsets_and_sizes() {
+        use super::super::values::{CssTopAuto, CssLeftSet, CssMaxWidthNone, CssMinHeightSet};
+        use super::super::util::float_to_css_fixed;
+
+        // Absolute so that libcss leaves the offsets as they were given
+        let sheet = "p { position: absolute; top: auto; left: 5px; max-width: none; min-height: 10% }";
+        do with_selected_style(sheet_params(None, None), sheet, "p") |computed| {
+            match computed.top() {
+                CssTopAuto => (),
+                _ => fail!(~"expected top to be auto")
+            }
+            match computed.left() {
+                CssLeftSet(CssUnitPx(left)) => assert!(left == float_to_css_fixed(5.0)),
+                _ => fail!(~"expected left to be a length")
+            }
+            match computed.max_width() {
+                CssMaxWidthNone => (),
+                _ => fail!(~"expected max-width to be none")
+            }
+            match computed.min_height() {
+                CssMinHeightSet(CssUnitPct(height)) => assert!(height == float_to_css_fixed(10.0)),
+                _ => fail!(~"expected min-height to be a percentage")
+            }
+        }
+    }
+
+    #[test]
+    fn outline() {
+        use super::super::values::{CssOutlineColorInvert, CssOutlineStyleDashed, CssOutlineWidthWidth};
+        use super::super::util::float_to_css_fixed;
+
+        let sheet = "p { outline: invert dashed 3px }";
+        do with_selected_style(sheet_params(None, None), sheet, "p") |computed| {
+            match computed.outline_color() {
+                CssOutlineColorInvert => (),
+                _ => fail!(~"expected the outline to invert")
+            }
+            assert!(computed.outline_style() as uint == CssOutlineStyleDashed as uint);
+            match computed.outline_width() {
+                CssOutlineWidthWidth(CssUnitPx(width)) => assert!(width == float_to_css_fixed(3.0)),
+                _ => fail!(~"expected the outline width as a length")
+            }
+        }
+    }
+
+    #[test]
+    fn list_style() {
+        use super::super::values::{CssListStyleTypeUpperRoman, CssListStylePositionInside};
+        use super::super::values::CssListStyleImageUri;
+
+        let sheet = "li { list-style: upper-roman inside url(http://example.com/bullet.png) }";
+        do with_selected_style(sheet_params(None, None), sheet, "li") |computed| {
+            assert!(computed.list_style_type() as uint == CssListStyleTypeUpperRoman as uint);
+            assert!(computed.list_style_position() as uint == CssListStylePositionInside as uint);
+            match computed.list_style_image() {
+                CssListStyleImageUri(url) => assert!(url.to_str_slice() == "http://example.com/bullet.png"),
+                _ => fail!(~"expected a list image")
+            }
+        }
+    }
+
+    #[test]
+    fn inline_text() {
+        use super::super::values::{CssVerticalAlignSet, CssVerticalAlignMiddle, CssLetterSpacingSet};
+        use super::super::values::{CssTextTransformUppercase, CssWhiteSpaceNowrap};
+        use super::super::util::float_to_css_fixed;
+
+        let sheet = "span { vertical-align: 3px; letter-spacing: 2px }
+                     span { text-transform: uppercase; white-space: nowrap }";
+        do with_selected_style(sheet_params(None, None), sheet, "span") |computed| {
+            match computed.vertical_align() {
+                CssVerticalAlignSet(CssUnitPx(length)) => assert!(length == float_to_css_fixed(3.0)),
+                _ => fail!(~"expected vertical-align to be a length")
+            }
+            match computed.letter_spacing() {
+                CssLetterSpacingSet(CssUnitPx(length)) => assert!(length == float_to_css_fixed(2.0)),
+                _ => fail!(~"expected letter-spacing to be a length")
+            }
+            assert!(computed.text_transform() as uint == CssTextTransformUppercase as uint);
+            assert!(computed.white_space() as uint == CssWhiteSpaceNowrap as uint);
+        }
+
+        do with_selected_style(sheet_params(None, None), "span { vertical-align: 50% }", "span") |computed| {
+            match computed.vertical_align() {
+                CssVerticalAlignSet(CssUnitPct(pct)) => assert!(pct == float_to_css_fixed(50.0)),
+                _ => fail!(~"expected vertical-align to be a percentage")
+            }
+        }
+
+        do with_selected_style(sheet_params(None, None), "span { vertical-align: middle }", "span") |computed| {
+            match computed.vertical_align() {
+                CssVerticalAlignMiddle => (),
+                _ => fail!(~"expected vertical-align to be middle")
+            }
+        }
+    }
+
+    #[test]
+    fn bidi() {
+        use super::super::values::{CssDirectionRtl, CssUnicodeBidiEmbed};
+
+        do with_selected_style(sheet_params(None, None), "p { direction: rtl; unicode-bidi: embed }", "p") |computed| {
+            assert!(computed.direction() as uint == CssDirectionRtl as uint);
+            assert!(computed.unicode_bidi() as uint == CssUnicodeBidiEmbed as uint);
+        }
+    }
+
+    #[test]
+    fn dynamic_pseudo_classes() {
+        use super::super::values::{CssColorColor, CssColorInherit};
+
+        // Were the handler asked about the wrong state, :focus would win instead
+        let sheet = "a:hover { color: #010203 } a:focus { color: #040506 }";
+        do with_selected_style(sheet_params(None, None), sheet, "a") |computed| {
+            match computed.color() {
+                CssColorColor(color) => assert!(color.r == 1 && color.g == 2 && color.b == 3),
+                CssColorInherit => fail!(~"expected the :hover color")
+            }
+        }
+    }
+
+    #[test]
+    fn lang_pseudo_class() {
+        let handler = SelectHandler { bogus: () };
+        let node = MyDomNode { name: @from_rust_string("p") };
+
+        assert!(handler.node_is_lang(&node, from_rust_string("en")));
+        assert!(handler.node_is_lang(&node, from_rust_string("EN-us")));
+        assert!(!handler.node_is_lang(&node, from_rust_string("en-GB")));
+        assert!(!handler.node_is_lang(&node, from_rust_string("e")));
+    }
+
+    #[test]
+    fn attribute_selectors() {
+        let handler = SelectHandler { bogus: () };
+        let node = MyDomNode { name: @from_rust_string("a") };
+        let lang = rust_str_to_net_qname("lang");
+        let href = rust_str_to_net_qname("href");
+        let rel = rust_str_to_net_qname("rel");
+        let title = rust_str_to_net_qname("title");
+
+        assert!(handler.node_has_attribute(&node, &href));
+        assert!(!handler.node_has_attribute(&node, &title));
+        assert!(handler.node_has_attribute_equal(&node, &lang, from_rust_string("en-US")));
+        assert!(!handler.node_has_attribute_equal(&node, &lang, from_rust_string("en")));
+        assert!(handler.node_has_attribute_dashmatch(&node, &lang, from_rust_string("en")));
+        assert!(!handler.node_has_attribute_dashmatch(&node, &lang, from_rust_string("en-U")));
+        assert!(handler.node_has_attribute_includes(&node, &rel, from_rust_string("noopener")));
+        assert!(!handler.node_has_attribute_includes(&node, &rel, from_rust_string("noop")));
+        assert!(handler.node_has_attribute_prefix(&node, &href, from_rust_string("http:")));
+        assert!(!handler.node_has_attribute_prefix(&node, &href, from_rust_string("")));
+        assert!(handler.node_has_attribute_suffix(&node, &href, from_rust_string(".pdf")));
+        assert!(handler.node_has_attribute_substring(&node, &href, from_rust_string("example")));
+        assert!(!handler.node_has_attribute_substring(&node, &title, from_rust_string("x")));
     }
 }
 
@@ -185,10 +719,402 @@ fn test_arc() {
         font: None,
     };
 
-    let sheet: CssStylesheet = css_stylesheet_create(&params);
+    let sheet: CssStylesheet = css_stylesheet_create(params).unwrap();
     let _arc = ARC(sheet);
 
     fn resolve_url(_base: &str, _rel: &LwcString) -> CssResult<LwcString> {
         fail!(~"resolving url");
     }
 }
+
+#[test]
+fn test_pending_imports() {
+    use stylesheet::*;
+    use types::CssLevel21;
+    use ll::types::{CSS_MEDIA_SCREEN, CSS_MEDIA_ALL};
+
+    // The sheets outlive this function, and so must the resolve closure libcss calls
+    // back into while they're parsed
+    fn new_sheet(url: ~str) -> CssStylesheet {
+        let resolve: CssUrlResolutionFn = |_base, rel| Ok(rel.clone());
+        let params = CssStylesheetParams {
+            params_version: CssStylesheetParamsVersion1,
+            level: CssLevel21,
+            charset: ~"UTF-8",
+            url: url,
+            title: ~"foo",
+            allow_quirks: false,
+            inline_style: false,
+            resolve: Some(resolve),
+            import: None,
+            color: None,
+            font: None,
+        };
+        css_stylesheet_create(params).unwrap()
+    }
+
+    fn new_child(url: ~str, data: &str) -> CssStylesheet {
+        let mut child = new_sheet(url);
+        child.append_data(data.as_bytes()).unwrap();
+        child.data_done().unwrap();
+        child
+    }
+
+    let mut sheet = new_sheet(~"parent.css");
+    let data = "@import url(first.css) screen; @import url(second.css); h1 { color: red; }";
+    sheet.append_data(data.as_bytes()).unwrap();
+    match sheet.data_done().unwrap() {
+        CssStylesheetImportsPending => (),
+        CssStylesheetComplete => fail!(~"expected a pending import")
+    }
+
+    // Imports come one at a time, in order, and each stays pending until it's registered
+    let (url, media) = sheet.next_pending_import().unwrap();
+    assert!(url.to_str_slice() == "first.css");
+    assert!(media == CSS_MEDIA_SCREEN);
+    let (again, _) = sheet.next_pending_import().unwrap();
+    assert!(again.to_str_slice() == "first.css");
+    sheet.register_import(new_child(~"first.css", "p { color: blue; }")).unwrap();
+
+    let (url, media) = sheet.next_pending_import().unwrap();
+    assert!(url.to_str_slice() == "second.css");
+    assert!(media == CSS_MEDIA_ALL);
+    sheet.register_import(new_child(~"second.css", "em { color: green; }")).unwrap();
+
+    assert!(sheet.next_pending_import().is_none());
+}
+
+#[test]
+fn test_default_ua_hints() {
+    use hint::*;
+    use types::CssColor;
+    use properties::{CssPropColor, CssPropDisplay};
+
+    let mut defaults = DefaultUaHints::new();
+    match defaults.hint_for(CssPropColor) {
+        CssHintColor(color) => assert!(color.a == 255 && color.r == 0 && color.g == 0 && color.b == 0),
+        _ => fail!(~"expected a color")
+    }
+    match defaults.hint_for(CssPropDisplay) {
+        CssHintUnknown => (),
+        _ => fail!(~"display has no UA-dependent initial value")
+    }
+
+    defaults.set(CssPropColor, CssHintColor(CssColor { a: 255, r: 0x33, g: 0x33, b: 0x33 }));
+    match defaults.hint_for(CssPropColor) {
+        CssHintColor(color) => assert!(color.r == 0x33),
+        _ => fail!(~"expected a color")
+    }
+}
+
+#[test]
+fn test_absolute_font_size() {
+    use hint::{CssHint, CssHintLength, CssHintKeyword};
+    use types::{CssUnitPx, CssUnitEm, CssUnitEx, CssUnitPct, CssUnitPt, CssUnitPc};
+    use util::{absolute_font_size, float_to_css_fixed};
+    use ll::properties::{CSS_FONT_SIZE_X_LARGE, CSS_FONT_SIZE_LARGER, CSS_FONT_SIZE_SMALLER};
+    use ll::stylesheet::css_fixed;
+    use std::libc::types::common::c99::uint8_t;
+
+    fn px_of(hint: CssHint) -> css_fixed {
+        match hint {
+            CssHintLength(CssUnitPx(px)) => px,
+            _ => fail!(~"expected a size in px")
+        }
+    }
+
+    // 9pt is 12px, and relative sizes scale from that rather than from 16px
+    let parent = CssHintLength(CssUnitPt(float_to_css_fixed(9.0)));
+    let em = CssHintLength(CssUnitEm(float_to_css_fixed(2.0)));
+    assert!(px_of(absolute_font_size(&parent, &em)) == float_to_css_fixed(24.0));
+    let ex = CssHintLength(CssUnitEx(float_to_css_fixed(2.0)));
+    assert!(px_of(absolute_font_size(&parent, &ex)) == float_to_css_fixed(12.0 * 2.0 * 0.6));
+    let pct = CssHintLength(CssUnitPct(float_to_css_fixed(50.0)));
+    assert!(px_of(absolute_font_size(&parent, &pct)) == float_to_css_fixed(6.0));
+
+    // Absolute children are converted to px too
+    let pc = CssHintLength(CssUnitPc(float_to_css_fixed(2.0)));
+    assert!(px_of(absolute_font_size(&parent, &pc)) == float_to_css_fixed(32.0));
+
+    // The relative keywords follow the parent and the absolute ones don't
+    let parent = CssHintLength(CssUnitPx(float_to_css_fixed(20.0)));
+    let larger = CssHintKeyword(CSS_FONT_SIZE_LARGER as uint8_t);
+    assert!(px_of(absolute_font_size(&parent, &larger)) == float_to_css_fixed(24.0));
+    let parent = CssHintLength(CssUnitPx(float_to_css_fixed(24.0)));
+    let smaller = CssHintKeyword(CSS_FONT_SIZE_SMALLER as uint8_t);
+    assert!(px_of(absolute_font_size(&parent, &smaller)) == float_to_css_fixed(20.0));
+    let x_large = CssHintKeyword(CSS_FONT_SIZE_X_LARGE as uint8_t);
+    assert!(px_of(absolute_font_size(&parent, &x_large)) == float_to_css_fixed(24.0));
+}
+
+#[test]
+fn test_hint_round_trip() {
+    use hint::*;
+    use types::*;
+    use properties::{CssFontFamilySerif, CssPropClomumnWidth, property_from_uint};
+    use properties::{CssPropWidth, CssPropFontFamily, CssPropVoiceFamily, CssPropColumnCount};
+    use properties::{CssPropColumnGap, CssPropColumnRuleWidth, CssPropColumnRuleColor};
+    use ll::hint::css_hint;
+    use ll::errors::{CSS_OK, CSS_BADPARM};
+    use ll::properties::{CSS_WIDTH_SET, CSS_FONT_FAMILY_SERIF, CSS_VOICE_FAMILY_FEMALE};
+    use wapcaplet::{LwcString, from_rust_string};
+    use std::libc::types::common::c99::{uint8_t, uint32_t};
+    use std::cast;
+    use std::uint;
+
+    // Enough of a hint to tell whether its value survived
+    fn describe(hint: &CssHint) -> ~str {
+        fn names(strings: &[LwcString]) -> ~[~str] {
+            strings.map(|s| s.to_str_slice().to_owned())
+        }
+
+        match *hint {
+            CssHintFontFamily(ref families, generic) => fmt!("font-family %? %u", names(*families), generic as uint),
+            CssHintLength(unit) => fmt!("length %?", unit),
+            CssHintColor(color) => fmt!("color %?", color),
+            CssHintKeyword(status) => fmt!("keyword %?", status),
+            CssHintFixed(value) => fmt!("fixed %?", value),
+            CssHintInteger(value) => fmt!("integer %?", value),
+            CssHintString(ref string) => fmt!("string %s", string.to_str_slice()),
+            CssHintStrings(status, ref strings) => fmt!("strings %? %?", status, names(*strings)),
+            CssHintCounters(ref counters) => {
+                fmt!("counters %?", counters.map(|c| (c.name.to_str_slice().to_owned(), c.value)))
+            }
+            CssHintClip(ref rect) => fmt!("clip %? %? %? %?", rect.top, rect.right, rect.bottom, rect.left),
+            CssHintPosition(h, v) => fmt!("position %? %?", h, v),
+            CssHintDefault => ~"default",
+            CssHintUnknown => ~"unknown"
+        }
+    }
+
+    let hints = ~[
+        CssHintKeyword(0),
+        CssHintKeyword(1),
+        CssHintKeyword(2),
+        CssHintKeyword(3),
+        CssHintLength(CssUnitPx(10 << 10)),
+        CssHintColor(CssColor { a: 255, r: 1, g: 2, b: 3 }),
+        CssHintFixed(1 << 10),
+        CssHintInteger(3),
+        CssHintString(from_rust_string("a.png")),
+        CssHintStrings(1, ~[from_rust_string("<"), from_rust_string(">")]),
+        CssHintFontFamily(~[from_rust_string("Foo")], CssFontFamilySerif),
+        CssHintCounters(~[CssCounter { name: from_rust_string("c"), value: 2 << 10 }]),
+        CssHintClip(CssClipRect { top: Some(CssUnitPx(1 << 10)), right: None,
+                                  bottom: Some(CssUnitEm(2 << 10)), left: None }),
+        CssHintPosition(CssUnitPct(50 << 10), CssUnitPx(0)),
+        CssHintDefault,
+        CssHintUnknown
+    ];
+
+    // Big enough for the union and the status byte that follows it
+    let mut buf = [0u64, ..4];
+    let llhint: *mut css_hint = unsafe { cast::transmute(&mut buf) };
+
+    for uint::range(0, CssPropClomumnWidth as uint + 1) |i| {
+        let property = property_from_uint(i as uint32_t);
+        for hints.iter().advance |hint| {
+            // Hints that don't fit the property are refused rather than failing
+            if hint.write_to_ll(property, llhint) != CSS_OK {
+                loop;
+            }
+
+            let expected = match *hint {
+                CssHintDefault => DefaultUaHints::new().hint_for(property),
+                _ => hint.clone()
+            };
+            let decoded = CssHint::new(property, llhint as *css_hint);
+            assert!(describe(&decoded) == describe(&expected));
+        }
+    }
+
+    // Properties with a value are refused the status that needs one as a keyword
+    assert!(CssHintKeyword(CSS_WIDTH_SET as uint8_t).write_to_ll(CssPropWidth, llhint) == CSS_BADPARM);
+    assert!(CssHintKeyword(CSS_FONT_FAMILY_SERIF as uint8_t).write_to_ll(CssPropFontFamily, llhint) == CSS_BADPARM);
+
+    // Every property that takes a value accepts one
+    let values = ~[
+        (CssPropColumnCount, CssHintInteger(3)),
+        (CssPropColumnGap, CssHintLength(CssUnitPx(1 << 10))),
+        (CssPropColumnRuleWidth, CssHintLength(CssUnitPx(1 << 10))),
+        (CssPropClomumnWidth, CssHintLength(CssUnitEm(1 << 10))),
+        (CssPropColumnRuleColor, CssHintColor(CssColor { a: 255, r: 1, g: 2, b: 3 })),
+        (CssPropVoiceFamily, CssHintStrings(CSS_VOICE_FAMILY_FEMALE as uint8_t, ~[from_rust_string("Alto")]))
+    ];
+    for values.iter().advance |&(property, ref hint)| {
+        assert!(hint.write_to_ll(property, llhint) == CSS_OK);
+        let decoded = CssHint::new(property, llhint as *css_hint);
+        assert!(describe(&decoded) == describe(hint));
+    }
+}
+
+// A flat run of sibling elements, for the default sibling methods on CssSelectHandler
+mod siblings {
+
+    use types::CssQName;
+    use select::CssSelectHandler;
+    use util::{VoidPtrLike, rust_str_to_net_qname};
+    use wapcaplet::LwcString;
+    use std::libc;
+    use std::cast;
+
+    static NAMES: [&'static str, ..5] = ["h1", "p", "h2", "P", "div"];
+
+    // An index into NAMES
+    struct ListNode {
+        index: uint
+    }
+
+    impl VoidPtrLike for ListNode {
+        fn from_void_ptr(node: *libc::c_void) -> ListNode {
+            assert!(node.is_not_null());
+            let index: uint = unsafe { cast::transmute(node) };
+            ListNode { index: index - 1 }
+        }
+
+        // Offset by one so the first node isn't null
+        fn to_void_ptr(&self) -> *libc::c_void {
+            unsafe { cast::transmute(self.index + 1) }
+        }
+    }
+
+    struct SiblingHandler {
+        bogus: ()
+    }
+
+    impl CssSelectHandler<ListNode> for SiblingHandler {
+        fn node_name(&self, node: &ListNode) -> CssQName {
+            rust_str_to_net_qname(NAMES[node.index])
+        }
+
+        fn node_classes(&self, _node: &ListNode) -> Option<~[LwcString]> { None }
+
+        fn node_id(&self, _node: &ListNode) -> Option<LwcString> { None }
+
+        fn named_parent_node(&self, _node: &ListNode, _qname: &CssQName) -> Option<ListNode> {
+            None
+        }
+
+        fn parent_node(&self, _node: &ListNode) -> Option<ListNode> {
+            None
+        }
+
+        fn sibling_node(&self, node: &ListNode) -> Option<ListNode> {
+            if node.index > 0 {
+                Some(ListNode { index: node.index - 1 })
+            } else {
+                None
+            }
+        }
+
+        fn next_sibling_node(&self, node: &ListNode) -> Option<ListNode> {
+            if node.index + 1 < NAMES.len() {
+                Some(ListNode { index: node.index + 1 })
+            } else {
+                None
+            }
+        }
+
+        // As if the div had content and everything else were empty
+        fn node_is_empty(&self, node: &ListNode) -> bool {
+            NAMES[node.index] != "div"
+        }
+
+        fn node_has_class(&self, _node: &ListNode, _name: LwcString) -> bool { false }
+
+        fn node_has_id(&self, _node: &ListNode, _name: LwcString) -> bool { false }
+
+        fn named_ancestor_node(&self, _node: &ListNode, _qname: &CssQName) -> Option<ListNode> {
+            None
+        }
+
+        fn node_is_root(&self, _node: &ListNode) -> bool { false }
+
+        fn node_is_link(&self, _node: &ListNode) -> bool { false }
+
+        fn node_is_visited(&self, _node: &ListNode) -> bool { false }
+    }
+
+    fn index_of(node: Option<ListNode>) -> Option<uint> {
+        node.map(|n| n.index)
+    }
+
+    #[test]
+    fn named_siblings() {
+        let handler = SiblingHandler { bogus: () };
+        let first = ListNode { index: 0 };
+        let second_p = ListNode { index: 3 };
+
+        assert!(index_of(handler.named_sibling_node(&second_p, &rust_str_to_net_qname("h2"))) == Some(2));
+        assert!(index_of(handler.named_sibling_node(&second_p, &rust_str_to_net_qname("H2"))) == Some(2));
+        assert!(index_of(handler.named_sibling_node(&second_p, &rust_str_to_net_qname("h1"))) == None);
+        assert!(index_of(handler.named_sibling_node(&first, &rust_str_to_net_qname("h1"))) == None);
+
+        assert!(index_of(handler.named_generic_sibling_node(&second_p, &rust_str_to_net_qname("h1"))) == Some(0));
+        assert!(index_of(handler.named_generic_sibling_node(&second_p, &rust_str_to_net_qname("p"))) == Some(1));
+        assert!(index_of(handler.named_generic_sibling_node(&second_p, &rust_str_to_net_qname("div"))) == None);
+        assert!(index_of(handler.named_generic_sibling_node(&first, &rust_str_to_net_qname("h1"))) == None);
+    }
+
+    #[test]
+    fn count_siblings() {
+        let handler = SiblingHandler { bogus: () };
+        let first = ListNode { index: 0 };
+        let first_p = ListNode { index: 1 };
+        let second_p = ListNode { index: 3 };
+        let last = ListNode { index: 4 };
+
+        // Every sibling, before and after
+        assert!(handler.node_count_siblings(&first, false, false) == 0);
+        assert!(handler.node_count_siblings(&first, false, true) == 4);
+        assert!(handler.node_count_siblings(&second_p, false, false) == 3);
+        assert!(handler.node_count_siblings(&second_p, false, true) == 1);
+        assert!(handler.node_count_siblings(&last, false, true) == 0);
+
+        // Only the ones with the same name, which ignores case
+        assert!(handler.node_count_siblings(&second_p, true, false) == 1);
+        assert!(handler.node_count_siblings(&second_p, true, true) == 0);
+        assert!(handler.node_count_siblings(&first_p, true, false) == 0);
+        assert!(handler.node_count_siblings(&first_p, true, true) == 1);
+        assert!(handler.node_count_siblings(&last, true, false) == 0);
+    }
+
+    #[test]
+    fn empty_pseudo_class() {
+        use stylesheet::*;
+        use select::{css_select_ctx_create, CssPseudoElementNone};
+        use values::{CssColorColor, CssColorInherit};
+        use types::CssLevel21;
+        use ll::types::{CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL, CSS_MEDIA_SCREEN};
+
+        let params = CssStylesheetParams {
+            params_version: CssStylesheetParamsVersion1,
+            level: CssLevel21,
+            charset: ~"UTF-8",
+            url: ~"foo",
+            title: ~"foo",
+            allow_quirks: false,
+            inline_style: false,
+            resolve: None,
+            import: None,
+            color: None,
+            font: None,
+        };
+        let sheet = css_stylesheet_create_from_str(params, ":empty { color: #0a0b0c }").unwrap();
+        let mut select_ctx = css_select_ctx_create().unwrap();
+        select_ctx.append_sheet(sheet, CSS_ORIGIN_AUTHOR, CSS_MEDIA_ALL).unwrap();
+
+        let handler = SiblingHandler { bogus: () };
+        let matches_empty = |index: uint| -> bool {
+            let node = ListNode { index: index };
+            let style = select_ctx.select_style(&node, CSS_MEDIA_SCREEN, None, &handler).unwrap();
+            match style.computed_style(CssPseudoElementNone).unwrap().color() {
+                CssColorColor(color) => color.r == 0x0a,
+                CssColorInherit => fail!(~"expected a color")
+            }
+        };
+
+        assert!(matches_empty(1));
+        assert!(!matches_empty(4));
+    }
+}
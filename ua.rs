@@ -0,0 +1,121 @@
+// Copyright 2013 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use CssResult;
+use stylesheet::{CssStylesheet, CssStylesheetParams, CssStylesheetParamsVersion1};
+use stylesheet::css_stylesheet_create_from_str;
+use types::CssLevel21;
+
+// The sample stylesheet from CSS 2.1 Appendix D, plus form controls
+pub static HTML_UA_SHEET: &'static str = "
+html, address,
+blockquote,
+body, dd, div,
+dl, dt, fieldset, form,
+frame, frameset,
+h1, h2, h3, h4,
+h5, h6, noframes,
+ol, p, ul, center,
+dir, hr, menu, pre   { display: block }
+li              { display: list-item }
+head            { display: none }
+table           { display: table }
+tr              { display: table-row }
+thead           { display: table-header-group }
+tbody           { display: table-row-group }
+tfoot           { display: table-footer-group }
+col             { display: table-column }
+colgroup        { display: table-column-group }
+td, th          { display: table-cell }
+caption         { display: table-caption }
+th              { font-weight: bolder; text-align: center }
+caption         { text-align: center }
+body            { margin: 8px }
+h1              { font-size: 2em; margin: .67em 0 }
+h2              { font-size: 1.5em; margin: .75em 0 }
+h3              { font-size: 1.17em; margin: .83em 0 }
+h4, p,
+blockquote, ul,
+fieldset, form,
+ol, dl, dir,
+menu            { margin: 1.12em 0 }
+h5              { font-size: .83em; margin: 1.5em 0 }
+h6              { font-size: .75em; margin: 1.67em 0 }
+h1, h2, h3, h4,
+h5, h6, b,
+strong          { font-weight: bolder }
+blockquote      { margin-left: 40px; margin-right: 40px }
+i, cite, em,
+var, address    { font-style: italic }
+pre, tt, code,
+kbd, samp       { font-family: monospace }
+pre             { white-space: pre }
+button, textarea,
+input, select   { display: inline-block }
+big             { font-size: 1.17em }
+small, sub, sup { font-size: .83em }
+sub             { vertical-align: sub }
+sup             { vertical-align: super }
+table           { border-spacing: 2px; }
+thead, tbody,
+tfoot           { vertical-align: middle }
+td, th, tr      { vertical-align: inherit }
+s, strike, del  { text-decoration: line-through }
+hr              { border: 1px inset }
+ol, ul, dir,
+menu, dd        { margin-left: 40px }
+ol              { list-style-type: decimal }
+ol ul, ul ol,
+ul ul, ol ol    { margin-top: 0; margin-bottom: 0 }
+u, ins          { text-decoration: underline }
+br:before       { content: \"\\A\"; white-space: pre-line }
+center          { text-align: center }
+:link, :visited { text-decoration: underline }
+:focus          { outline: thin dotted invert }
+
+BDO[DIR=\"ltr\"]  { direction: ltr; unicode-bidi: bidi-override }
+BDO[DIR=\"rtl\"]  { direction: rtl; unicode-bidi: bidi-override }
+
+*[DIR=\"ltr\"]    { direction: ltr; unicode-bidi: embed }
+*[DIR=\"rtl\"]    { direction: rtl; unicode-bidi: embed }
+
+fieldset        { margin-left: 2px; margin-right: 2px;
+                  padding: .35em .625em .75em;
+                  border: 2px groove }
+legend          { padding-left: 2px; padding-right: 2px }
+input, select,
+textarea,
+button          { font-size: .83em; margin: 0 }
+textarea        { font-family: monospace; white-space: pre-wrap }
+input[type=\"hidden\"] { display: none }
+input[type=\"checkbox\"],
+input[type=\"radio\"] { margin: 3px 3px 0 5px }
+button,
+input[type=\"button\"],
+input[type=\"submit\"],
+input[type=\"reset\"] { padding: 1px 6px; border: 2px outset }
+";
+
+pub fn html_ua_stylesheet() -> CssResult<CssStylesheet> {
+    let params = CssStylesheetParams {
+        params_version: CssStylesheetParamsVersion1,
+        level: CssLevel21,
+        charset: ~"UTF-8",
+        url: ~"about:ua",
+        title: ~"",
+        allow_quirks: false,
+        inline_style: false,
+        resolve: None,
+        import: None,
+        color: None,
+        font: None,
+    };
+
+    css_stylesheet_create_from_str(params, HTML_UA_SHEET)
+}
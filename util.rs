@@ -8,7 +8,13 @@
 // except according to those terms.
 
 use ll::stylesheet::css_fixed;
-use types::CssQName;
+use ll::properties::{css_font_size_e, CSS_FONT_SIZE_INHERIT, CSS_FONT_SIZE_XX_SMALL};
+use ll::properties::{CSS_FONT_SIZE_X_SMALL, CSS_FONT_SIZE_SMALL, CSS_FONT_SIZE_LARGE};
+use ll::properties::{CSS_FONT_SIZE_X_LARGE, CSS_FONT_SIZE_XX_LARGE};
+use ll::properties::{CSS_FONT_SIZE_LARGER, CSS_FONT_SIZE_SMALLER};
+use types::{CssQName, CssUnit, CssUnitPx, CssUnitEx, CssUnitEm, CssUnitIn, CssUnitCm};
+use types::{CssUnitMm, CssUnitPt, CssUnitPc, CssUnitPct};
+use hint::{CssHint, CssHintLength, CssHintKeyword};
 use std::libc::c_void;
 use lwcstr_from_rust_str = wapcaplet::from_rust_string;
 
@@ -22,6 +28,60 @@ pub fn float_to_css_fixed(f: float) -> css_fixed {
     (f * ((1 << BEFORE) as float)) as css_fixed
 }
 
+// Converts an absolute length to px at 96px to the inch. Relative and
+// non-length units have no fixed size and give None
+pub fn absolute_length_to_px(unit: CssUnit) -> Option<float> {
+    match unit {
+        CssUnitPx(px) => Some(css_fixed_to_float(px)),
+        CssUnitIn(inches) => Some(css_fixed_to_float(inches) * 96.0),
+        CssUnitCm(cm) => Some(css_fixed_to_float(cm) * 96.0 / 2.54),
+        CssUnitMm(mm) => Some(css_fixed_to_float(mm) * 96.0 / 25.4),
+        CssUnitPt(pt) => Some(css_fixed_to_float(pt) * 96.0 / 72.0),
+        CssUnitPc(pc) => Some(css_fixed_to_float(pc) * 16.0),
+        _ => None
+    }
+}
+
+// Resolves a font-size hint to a size in px. The parent's size is already
+// absolute, and relative sizes and the relative keywords scale from it
+pub fn absolute_font_size(parent: &CssHint, child: &CssHint) -> CssHint {
+    static MEDIUM_PX: float = 16.0;
+
+    let parent_px = match *parent {
+        CssHintLength(unit) => match absolute_length_to_px(unit) {
+            Some(px) => px,
+            None => MEDIUM_PX
+        },
+        _ => MEDIUM_PX
+    };
+    let px = match *child {
+        CssHintLength(CssUnitEm(em)) => parent_px * css_fixed_to_float(em),
+        // libcss makes the same guess about x-heights
+        CssHintLength(CssUnitEx(ex)) => parent_px * css_fixed_to_float(ex) * 0.6,
+        CssHintLength(CssUnitPct(pct)) => parent_px * css_fixed_to_float(pct) / 100.0,
+        CssHintLength(unit) => match absolute_length_to_px(unit) {
+            Some(px) => px,
+            None => return CssHintLength(unit)
+        },
+        CssHintKeyword(status) => {
+            let status = status as css_font_size_e;
+            // The CSS 2.1 scaling factors between neighbouring sizes
+            if status == CSS_FONT_SIZE_XX_SMALL { MEDIUM_PX * 3.0 / 5.0 }
+            else if status == CSS_FONT_SIZE_X_SMALL { MEDIUM_PX * 3.0 / 4.0 }
+            else if status == CSS_FONT_SIZE_SMALL { MEDIUM_PX * 8.0 / 9.0 }
+            else if status == CSS_FONT_SIZE_LARGE { MEDIUM_PX * 6.0 / 5.0 }
+            else if status == CSS_FONT_SIZE_X_LARGE { MEDIUM_PX * 3.0 / 2.0 }
+            else if status == CSS_FONT_SIZE_XX_LARGE { MEDIUM_PX * 2.0 }
+            else if status == CSS_FONT_SIZE_LARGER { parent_px * 1.2 }
+            else if status == CSS_FONT_SIZE_SMALLER { parent_px / 1.2 }
+            else if status == CSS_FONT_SIZE_INHERIT { parent_px }
+            else { MEDIUM_PX }
+        }
+        _ => parent_px
+    };
+    CssHintLength(CssUnitPx(float_to_css_fixed(px)))
+}
+
 pub fn rust_str_to_net_qname(s: &str) -> CssQName {
     CssQName {
         ns: None,